@@ -1,38 +1,529 @@
+use std::collections::{HashMap, HashSet};
 use std::env;
 use std::fs;
 use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
-use walkdir::WalkDir;
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::sync::Mutex;
+use std::time::Duration;
 use chrono::Utc;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::{WalkBuilder, WalkState};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use rand::Rng;
+use serde::Deserialize;
+
+/// How long the watcher waits for a quiet period after the last filesystem event
+/// before processing everything it has collected, so a burst of writes to one
+/// file (editor save, git checkout) only triggers one archive pass.
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(500);
+
+const CONFIG_NAME: &str = "cleanup.toml";
+
+/// On-disk shape of `cleanup.toml`. Every field is optional so a config can tweak
+/// just the keys it cares about; anything left out keeps the built-in default.
+#[derive(Deserialize, Default)]
+struct CleanupConfig {
+    input: Option<PathBuf>,
+    archive: Option<PathBuf>,
+    include: Option<Vec<String>>,
+    exclude: Option<Vec<String>>,
+    keep_directory_structure: Option<bool>,
+}
+
+/// Loads `cleanup.toml` from the workspace root, if present. A missing file is the
+/// normal case and silently yields defaults; a present-but-invalid file is an error.
+fn load_config(root: &Path) -> CleanupConfig {
+    let path = root.join(CONFIG_NAME);
+    match fs::read_to_string(&path) {
+        Ok(content) => toml::from_str(&content).unwrap_or_else(|e| {
+            eprintln!("Failed to parse {}: {}", path.display(), e);
+            std::process::exit(1);
+        }),
+        Err(_) => CleanupConfig::default(),
+    }
+}
+
+fn compile_patterns(patterns: &[String], flag: &str) -> Vec<glob::Pattern> {
+    patterns
+        .iter()
+        .map(|p| {
+            glob::Pattern::new(p).unwrap_or_else(|e| {
+                eprintln!("Invalid {} glob '{}': {}", flag, p, e);
+                std::process::exit(1);
+            })
+        })
+        .collect()
+}
+
+/// Filename patterns the current run archives against, e.g. `*.bak` or `*legacy*`.
+/// Falls back to the tool's historical filename heuristic when no config overrides it.
+const DEFAULT_INCLUDE_PATTERNS: &[&str] = &["*.bak", "*unused*", "*legacy*"];
+
+/// Resolved options for one cleanup run, merging `cleanup.toml` with CLI flags
+/// (CLI always wins for a key it sets explicitly).
+struct CleanupOptions {
+    dry_run: bool,
+    no_ignore: bool,
+    analyze: bool,
+    dead_code_threshold: f64,
+    include: Vec<glob::Pattern>,
+    exclude: Vec<glob::Pattern>,
+    keep_directory_structure: bool,
+}
+
+/// Compiler lints that indicate a source file is dead weight rather than merely
+/// named like a leftover (`unused`/`legacy`/`.bak`).
+const DEAD_CODE_LINTS: &[&str] = &["dead_code", "unused_imports"];
+
+/// Fraction of a file's items that must be flagged dead before `--analyze`
+/// proposes archiving it. 1.0 means every item in the file must be unused.
+const DEFAULT_DEAD_CODE_THRESHOLD: f64 = 1.0;
+
+const ARCHIVE_LOG_NAME: &str = "ARCHIVE_LOG.txt";
+
+/// A single parsed line from `ARCHIVE_LOG.txt`.
+struct LogEntry {
+    run_id: String,
+    action: String,
+    from: String,
+    to: String,
+}
+
+/// Generates a run ID for tagging every log entry produced by one invocation:
+/// a UTC timestamp plus a random suffix so concurrent runs never collide.
+fn generate_run_id() -> String {
+    let suffix: String = rand::thread_rng()
+        .sample_iter(rand::distributions::Alphanumeric)
+        .take(6)
+        .map(char::from)
+        .collect();
+    format!("{}-{}", Utc::now().format("%Y%m%dT%H%M%S"), suffix)
+}
+
+fn append_log(archive: &Path, run_id: &str, action: &str, from: &Path, to: &Path) -> io::Result<()> {
+    let mut log = fs::OpenOptions::new().append(true).create(true).open(archive.join(ARCHIVE_LOG_NAME))?;
+    writeln!(
+        log,
+        "{} [{}] {} {} to {} by cleanup tool",
+        Utc::now(),
+        run_id,
+        action,
+        from.display(),
+        to.display()
+    )
+}
+
+/// Parses one `ARCHIVE_LOG.txt` line of the form
+/// `<timestamp> [<run_id>] <action> <from> to <to> by cleanup tool`.
+fn parse_log_line(line: &str) -> Option<LogEntry> {
+    let (head, tail) = line.split_once("] ")?;
+    let run_id = head.rsplit('[').next()?.to_string();
+    let tail = tail.strip_suffix(" by cleanup tool")?;
+    let (action, rest) = tail.split_once(' ')?;
+    let (from, to) = rest.split_once(" to ")?;
+    Some(LogEntry { run_id, action: action.to_string(), from: from.to_string(), to: to.to_string() })
+}
+
+fn read_log_entries(archive: &Path) -> Vec<LogEntry> {
+    fs::read_to_string(archive.join(ARCHIVE_LOG_NAME))
+        .unwrap_or_default()
+        .lines()
+        .filter_map(parse_log_line)
+        .collect()
+}
+
+const JOURNAL_NAME: &str = "JOURNAL.txt";
+
+/// One planned move in the intent journal: `(from, to)` plus whether it has
+/// actually happened yet. The journal only exists while a run is in flight or
+/// was interrupted; a clean finish removes it.
+#[derive(Clone)]
+struct JournalEntry {
+    from: PathBuf,
+    to: PathBuf,
+    done: bool,
+}
+
+fn journal_path(archive: &Path) -> PathBuf {
+    archive.join(JOURNAL_NAME)
+}
+
+fn write_journal(archive: &Path, entries: &[JournalEntry]) -> io::Result<()> {
+    fs::create_dir_all(archive)?;
+    let mut out = String::new();
+    for entry in entries {
+        out.push_str(&format!(
+            "{}\t{}\t{}\n",
+            entry.from.display(),
+            entry.to.display(),
+            if entry.done { "DONE" } else { "PLANNED" }
+        ));
+    }
+    fs::write(journal_path(archive), out)
+}
+
+fn load_journal(archive: &Path) -> Option<Vec<JournalEntry>> {
+    let content = fs::read_to_string(journal_path(archive)).ok()?;
+    Some(
+        content
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.splitn(3, '\t');
+                let from = PathBuf::from(fields.next()?);
+                let to = PathBuf::from(fields.next()?);
+                let done = fields.next()? == "DONE";
+                Some(JournalEntry { from, to, done })
+            })
+            .collect(),
+    )
+}
+
+fn clear_journal(archive: &Path) -> io::Result<()> {
+    let path = journal_path(archive);
+    if path.exists() {
+        fs::remove_file(path)
+    } else {
+        Ok(())
+    }
+}
+
+/// IO errors worth retrying a few times before giving up, as opposed to ones that
+/// indicate the tree itself is in a genuinely inconsistent state.
+fn is_transient_io_error(error: &io::Error) -> bool {
+    matches!(error.kind(), io::ErrorKind::Interrupted | io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut)
+}
+
+const RENAME_RETRIES: u32 = 3;
+
+fn rename_with_retry(src: &Path, dest: &Path) -> io::Result<()> {
+    let mut attempts = 0;
+    loop {
+        match fs::rename(src, dest) {
+            Ok(()) => return Ok(()),
+            Err(e) if is_transient_io_error(&e) && attempts < RENAME_RETRIES => {
+                attempts += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Finishes or undoes an interrupted run using its leftover journal.
+/// `rollback = false` completes the remaining planned moves; `rollback = true`
+/// undoes every move that already happened (marked `DONE`, or whose file is found at
+/// its destination and not at its source), returning the tree to its pre-run state.
+fn recover_journal(root: &Path, archive: &Path, rollback: bool, run_id: &str) -> io::Result<()> {
+    let Some(entries) = load_journal(archive) else {
+        eprintln!("No incomplete journal found at {}.", journal_path(archive).display());
+        return Ok(());
+    };
+
+    if rollback {
+        let mut undone = 0;
+        // A move can finish just before its entry is marked DONE, so every entry is
+        // checked against the filesystem rather than trusting the DONE flag alone.
+        for entry in &entries {
+            if entry.to.exists() && !entry.from.exists() {
+                fs::create_dir_all(entry.from.parent().unwrap())?;
+                rename_with_retry(&entry.to, &entry.from)?;
+                undone += 1;
+            }
+        }
+        println!("Rolled back {} completed move(s).", undone);
+    } else {
+        let mut finished = 0;
+        for entry in entries.iter().filter(|e| !e.done) {
+            if entry.from.exists() {
+                fs::create_dir_all(entry.to.parent().unwrap())?;
+                rename_with_retry(&entry.from, &entry.to)?;
+                // Log the completed move the same way a normal run would, under this
+                // recovery's own run ID: `restore` of that ID reverts the rolled-forward
+                // moves, while moves finished before the interruption stay under the
+                // interrupted run's ID.
+                let rel_from = entry.from.strip_prefix(root).unwrap_or(&entry.from);
+                append_log(archive, run_id, "archived", rel_from, &entry.to)?;
+                finished += 1;
+            }
+        }
+        println!("Rolled forward {} remaining move(s).", finished);
+    }
+    clear_journal(archive)
+}
 
 fn is_git_clean() -> bool {
-    let output = Command::new("git").args(&["status", "--porcelain"]).output().unwrap();
+    let output = Command::new("git").args(["status", "--porcelain"]).output().unwrap();
     output.stdout.is_empty()
 }
 
-fn find_unused_code(root: &Path, archive: &Path, dry_run: bool) -> io::Result<()> {
-    for entry in WalkDir::new(root).into_iter().filter_map(|e| e.ok()) {
-        let path = entry.path();
-        if path.starts_with(archive) { continue; }
-        if path.is_file() {
-            let metadata = fs::metadata(path)?;
-            if metadata.len() == 0 { continue; }
+/// Matches a `--exclude` glob against a path relative to `root`.
+fn is_excluded(rel_path: &Path, excludes: &[glob::Pattern]) -> bool {
+    excludes.iter().any(|pattern| pattern.matches_path(rel_path))
+}
+
+fn is_candidate(filename: &str, include: &[glob::Pattern]) -> bool {
+    include.iter().any(|pattern| pattern.matches(filename))
+}
+
+/// Computes where a candidate should land under `archive`. When `keep_structure` is
+/// true the relative tree is mirrored (the tool's original behavior); otherwise
+/// every file is flattened directly into `archive`, with a numeric suffix inserted
+/// on filename collisions so two same-named files from different directories don't
+/// clobber each other. `reserved` holds destinations already handed out earlier in
+/// the same batch — checking `dest.exists()` alone isn't enough there, since every
+/// flatten destination in a batch is computed before any file has actually moved.
+fn archive_dest_path(archive: &Path, rel_path: &Path, keep_structure: bool, reserved: &HashSet<PathBuf>) -> PathBuf {
+    if keep_structure {
+        return archive.join(rel_path);
+    }
+    let stem = rel_path.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+    let ext = rel_path.extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default();
+    let mut dest = archive.join(format!("{}{}", stem, ext));
+    let mut suffix = 1;
+    while dest.exists() || reserved.contains(&dest) {
+        dest = archive.join(format!("{}_{}{}", stem, suffix, ext));
+        suffix += 1;
+    }
+    dest
+}
+
+/// Runs `cargo build --message-format=json` and counts `dead_code`/`unused_imports`
+/// diagnostics per source file. Returns an empty map (with a warning on stderr) if
+/// the workspace doesn't build, since dead-code analysis needs compiler output.
+fn scan_dead_code_warnings(root: &Path) -> HashMap<PathBuf, usize> {
+    let mut counts = HashMap::new();
+    let output = match Command::new("cargo")
+        .args(["build", "--message-format=json"])
+        .current_dir(root)
+        .output()
+    {
+        Ok(output) => output,
+        Err(e) => {
+            eprintln!("--analyze: failed to run cargo build: {}", e);
+            return counts;
+        }
+    };
+    if !output.status.success() {
+        eprintln!("--analyze: cargo build failed; skipping dead-code analysis");
+        return counts;
+    }
+
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let Ok(msg) = serde_json::from_str::<serde_json::Value>(line) else { continue };
+        if msg.get("reason").and_then(|r| r.as_str()) != Some("compiler-message") {
+            continue;
+        }
+        let Some(message) = msg.get("message") else { continue };
+        let code = message.get("code").and_then(|c| c.get("code")).and_then(|c| c.as_str()).unwrap_or("");
+        if !DEAD_CODE_LINTS.contains(&code) {
+            continue;
+        }
+        let Some(spans) = message.get("spans").and_then(|s| s.as_array()) else { continue };
+        // A single diagnostic can carry several spans pointing at the same file
+        // (primary span plus macro-expansion spans); count it once per file per
+        // diagnostic so the warning count stays in the same unit as `count_items`.
+        let files: HashSet<PathBuf> = spans
+            .iter()
+            .filter_map(|span| span.get("file_name").and_then(|f| f.as_str()))
+            .map(|file_name| root.join(file_name))
+            .collect();
+        for file in files {
+            *counts.entry(file).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+/// Rough count of items in a source file: every line that starts with an item keyword
+/// once indentation and a `pub`/`pub(crate)` prefix are stripped, so methods and other
+/// nested items count as well as top-level ones. Used as the denominator when
+/// deciding what fraction of a file's items the compiler flagged as dead.
+fn count_items(path: &Path) -> usize {
+    const ITEM_PREFIXES: &[&str] =
+        &["fn ", "struct ", "enum ", "trait ", "impl ", "const ", "static ", "mod ", "use "];
+    fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .filter(|line| {
+            let line = line.trim_start().trim_start_matches("pub(crate) ").trim_start_matches("pub ");
+            ITEM_PREFIXES.iter().any(|prefix| line.starts_with(prefix))
+        })
+        .count()
+}
+
+/// Files whose dead-code warning ratio meets `threshold`, derived from a compiler
+/// warning scan. A file with zero counted items never qualifies, since a ratio
+/// against zero items isn't meaningful evidence that the whole file is unused.
+fn dead_code_candidates(warning_counts: &HashMap<PathBuf, usize>, threshold: f64) -> Vec<PathBuf> {
+    warning_counts
+        .iter()
+        .filter_map(|(path, &warnings)| {
+            let items = count_items(path);
+            if items == 0 {
+                return None;
+            }
+            let ratio = warnings as f64 / items as f64;
+            (ratio >= threshold).then(|| path.clone())
+        })
+        .collect()
+}
+
+/// True if any path component is `.git` — the tool must never move a file out of
+/// the repository's own git directory, no matter what `--no-ignore`/`hidden` say.
+fn is_inside_git_dir(path: &Path) -> bool {
+    path.components().any(|c| c.as_os_str() == ".git")
+}
+
+/// Walks `root` in parallel, returning the relative paths of files that match the
+/// archive heuristics. Discovery only reads the filesystem; no moves happen here, and
+/// the only state the worker threads share is the mutex-guarded list of matches.
+fn scan_candidates(root: &Path, archive: &Path, opts: &CleanupOptions) -> Vec<PathBuf> {
+    let mut builder = WalkBuilder::new(root);
+    builder
+        // Leaving `hidden` at the crate default (true) when honoring ignore rules is
+        // what makes the walk skip `.git` in the first place; `--no-ignore` restores
+        // the old exhaustive walk, including dotfiles, so it flips this too.
+        .hidden(!opts.no_ignore)
+        .git_ignore(!opts.no_ignore)
+        .git_global(!opts.no_ignore)
+        .git_exclude(!opts.no_ignore)
+        .ignore(!opts.no_ignore);
+
+    let found = Mutex::new(Vec::new());
+    builder.build_parallel().run(|| {
+        Box::new(|entry| {
+            let Ok(entry) = entry else { return WalkState::Continue };
+            let path = entry.path();
+            // Unconditional, regardless of --no-ignore/hidden: never descend into
+            // or archive anything under .git/.
+            if is_inside_git_dir(path) {
+                return WalkState::Skip;
+            }
+            if path.starts_with(archive) {
+                return WalkState::Continue;
+            }
+            if !path.is_file() {
+                return WalkState::Continue;
+            }
+            let Ok(metadata) = fs::metadata(path) else { return WalkState::Continue };
+            if metadata.len() == 0 {
+                return WalkState::Continue;
+            }
+            let rel_path = path.strip_prefix(root).unwrap().to_path_buf();
+            if is_excluded(&rel_path, &opts.exclude) {
+                return WalkState::Continue;
+            }
             let filename = path.file_name().unwrap().to_string_lossy();
-            if filename.ends_with(".bak") || filename.contains("unused") || filename.contains("legacy") {
-                let rel_path = path.strip_prefix(root).unwrap();
-                let dest = archive.join(rel_path);
-                if dry_run {
-                    println!("Would archive: {}", rel_path.display());
-                } else {
-                    fs::create_dir_all(dest.parent().unwrap())?;
-                    fs::rename(path, &dest)?;
-                    let mut log = fs::OpenOptions::new().append(true).create(true).open(archive.join("ARCHIVE_LOG.txt"))?;
-                    writeln!(log, "{} archived {} to {} by cleanup tool", Utc::now(), rel_path.display(), dest.display())?;
+            if is_candidate(&filename, &opts.include) {
+                found.lock().unwrap().push(rel_path);
+            }
+            WalkState::Continue
+        })
+    });
+
+    let mut candidates = found.into_inner().unwrap();
+    candidates.sort();
+    candidates
+}
+
+fn find_unused_code(root: &Path, archive: &Path, run_id: &str, opts: &CleanupOptions) -> io::Result<()> {
+    let mut candidates = scan_candidates(root, archive, opts);
+
+    if opts.analyze {
+        let warning_counts = scan_dead_code_warnings(root);
+        if opts.dry_run {
+            let mut report: Vec<(&PathBuf, &usize)> = warning_counts.iter().collect();
+            report.sort();
+            for (path, warnings) in report {
+                println!("{}: {} dead-code warning(s)", path.strip_prefix(root).unwrap_or(path).display(), warnings);
+            }
+        }
+        for path in dead_code_candidates(&warning_counts, opts.dead_code_threshold) {
+            if let Ok(rel_path) = path.strip_prefix(root) {
+                let rel_path = rel_path.to_path_buf();
+                if !candidates.contains(&rel_path) && !is_excluded(&rel_path, &opts.exclude) {
+                    candidates.push(rel_path);
                 }
             }
         }
+        candidates.sort();
+    }
+
+    if opts.dry_run {
+        for rel_path in &candidates {
+            println!("Would archive: {}", rel_path.display());
+        }
+        return Ok(());
+    }
+
+    // Write the full intent journal before moving anything, so a mid-run interruption
+    // leaves behind a complete record of what was planned versus what finished. Track
+    // destinations as they're allocated so flatten mode can't hand out the same path
+    // to two candidates before either has actually moved.
+    let mut reserved: HashSet<PathBuf> = HashSet::new();
+    let mut journal: Vec<JournalEntry> = Vec::with_capacity(candidates.len());
+    for rel_path in &candidates {
+        let dest = archive_dest_path(archive, rel_path, opts.keep_directory_structure, &reserved);
+        reserved.insert(dest.clone());
+        journal.push(JournalEntry { from: root.join(rel_path), to: dest, done: false });
+    }
+    write_journal(archive, &journal)?;
+
+    // Moves and log writes happen serially, in sorted order, so ARCHIVE_LOG.txt stays
+    // deterministic regardless of how the parallel scan discovered each candidate.
+    for (i, rel_path) in candidates.iter().enumerate() {
+        let dest = &journal[i].to;
+        fs::create_dir_all(dest.parent().unwrap())?;
+        rename_with_retry(&journal[i].from, dest)?;
+        append_log(archive, run_id, "archived", rel_path, dest)?;
+        journal[i].done = true;
+        write_journal(archive, &journal)?;
+    }
+    clear_journal(archive)?;
+    Ok(())
+}
+
+/// Reverts one archive run recorded in `ARCHIVE_LOG.txt`, moving every file it
+/// archived back to its original location. Defaults to the most recent run when
+/// `run_id` is `None`. Entries whose archived copy has vanished, or whose original
+/// path has since been recreated, are skipped with a warning rather than clobbered.
+fn restore_run(root: &Path, archive: &Path, run_id: Option<&str>) -> io::Result<()> {
+    let entries = read_log_entries(archive);
+
+    let target_run = match run_id {
+        Some(id) => id.to_string(),
+        None => entries
+            .iter()
+            .rev()
+            .find(|e| e.action == "archived")
+            .map(|e| e.run_id.clone())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no archive runs found in ARCHIVE_LOG.txt"))?,
+    };
+
+    let to_restore: Vec<&LogEntry> =
+        entries.iter().filter(|e| e.run_id == target_run && e.action == "archived").collect();
+    if to_restore.is_empty() {
+        eprintln!("No archived entries found for run {}", target_run);
+        return Ok(());
+    }
+
+    for entry in to_restore {
+        let original = root.join(&entry.from);
+        let archived = PathBuf::from(&entry.to);
+        if !archived.exists() {
+            eprintln!("Skipping {}: archived copy {} is missing", entry.from, archived.display());
+            continue;
+        }
+        if original.exists() {
+            eprintln!("Skipping {}: original path already exists", entry.from);
+            continue;
+        }
+        fs::create_dir_all(original.parent().unwrap())?;
+        fs::rename(&archived, &original)?;
+        append_log(archive, &target_run, "restored", &archived, &original)?;
     }
+    println!("Restored run {}.", target_run);
     Ok(())
 }
 
@@ -41,16 +532,201 @@ fn rebuild_workspace() -> bool {
     status.success()
 }
 
+/// Moves a single file matched during watch mode. Unlike `find_unused_code`'s batch
+/// run, one incremental move isn't worth journaling: there's no multi-file operation
+/// to recover mid-flight, just an append to `ARCHIVE_LOG.txt` for the audit trail.
+fn archive_one(root: &Path, archive: &Path, rel_path: &Path, run_id: &str, keep_structure: bool) -> io::Result<()> {
+    let dest = archive_dest_path(archive, rel_path, keep_structure, &HashSet::new());
+    fs::create_dir_all(dest.parent().unwrap())?;
+    fs::rename(root.join(rel_path), &dest)?;
+    append_log(archive, run_id, "archived", rel_path, &dest)
+}
+
+/// Builds a `.gitignore`-aware matcher for watch mode, which gets individual paths
+/// from filesystem events rather than a directory walk and so can't reuse `WalkBuilder`.
+/// Loads every `.gitignore` in the tree (not just the root one) into a single matcher
+/// rooted at `root`, so a nested file's patterns are matched against paths relative
+/// to `root` rather than to that file's own directory. `.ignore` files and global git
+/// excludes, which the batch walk also honors, are not loaded.
+fn build_gitignore(root: &Path) -> Gitignore {
+    let mut builder = GitignoreBuilder::new(root);
+    for entry in WalkBuilder::new(root).hidden(false).build().filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if is_inside_git_dir(path) {
+            continue;
+        }
+        if path.file_name().is_some_and(|n| n == ".gitignore") {
+            let _ = builder.add(path);
+        }
+    }
+    builder.build().unwrap_or_else(|_| Gitignore::empty())
+}
+
+fn handle_watch_path(path: &Path, root: &Path, archive: &Path, opts: &CleanupOptions, run_id: &str, gitignore: &Gitignore) {
+    // Unconditional, like the batch walk: never touch anything under .git/, no
+    // matter what ignore rules or --no-ignore say.
+    if is_inside_git_dir(path) {
+        return;
+    }
+    if path.starts_with(archive) {
+        return; // ignore writes under archive/ itself to avoid a feedback loop
+    }
+    if !path.is_file() {
+        return;
+    }
+    let Ok(rel_path) = path.strip_prefix(root) else { return };
+    if !opts.no_ignore && gitignore.matched_path_or_any_parents(rel_path, false).is_ignore() {
+        return;
+    }
+    if is_excluded(rel_path, &opts.exclude) {
+        return;
+    }
+    let Some(filename) = path.file_name() else { return };
+    if !is_candidate(&filename.to_string_lossy(), &opts.include) {
+        return;
+    }
+    match archive_one(root, archive, rel_path, run_id, opts.keep_directory_structure) {
+        Ok(()) => println!("Archived (watch): {}", rel_path.display()),
+        Err(e) => eprintln!("Failed to archive {}: {}", rel_path.display(), e),
+    }
+}
+
+/// Runs an initial scan, then watches `root` for newly created or renamed files and
+/// archives any that match the cleanup rules, without re-walking the whole tree.
+/// Events are debounced by `WATCH_DEBOUNCE` so a single save doesn't fire twice.
+fn run_watch(root: &Path, archive: &Path, opts: &CleanupOptions, run_id: &str) -> notify::Result<()> {
+    if let Err(e) = find_unused_code(root, archive, run_id, opts) {
+        eprintln!("Error during initial scan: {}", e);
+    }
+
+    println!("Watching {} for newly created files matching cleanup rules (Ctrl-C to stop)...", root.display());
+    // Event paths from notify can be absolute even when `root` is relative, so event
+    // paths, `root` and `archive` are all made absolute before they are compared.
+    let cwd = env::current_dir().map_err(notify::Error::io)?;
+    let root = &cwd.join(root);
+    let archive = &cwd.join(archive);
+    let gitignore = build_gitignore(root);
+    let (tx, rx) = channel();
+    let mut watcher: RecommendedWatcher = notify::recommended_watcher(move |res| {
+        let _ = tx.send(res);
+    })?;
+    watcher.watch(root, RecursiveMode::Recursive)?;
+
+    let mut pending: HashSet<PathBuf> = HashSet::new();
+    loop {
+        match rx.recv_timeout(WATCH_DEBOUNCE) {
+            Ok(Ok(event)) => {
+                if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                    pending.extend(event.paths);
+                }
+            }
+            Ok(Err(e)) => eprintln!("Watch error: {}", e),
+            Err(RecvTimeoutError::Timeout) => {
+                for path in pending.drain() {
+                    handle_watch_path(&cwd.join(&path), root, archive, opts, run_id, &gitignore);
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => return Ok(()),
+        }
+    }
+}
+
 fn main() {
     let args: Vec<String> = env::args().collect();
+    let config = load_config(Path::new("."));
+    let root = args
+        .iter()
+        .zip(args.iter().skip(1))
+        .find(|(flag, _)| *flag == "--input")
+        .map(|(_, value)| PathBuf::from(value))
+        .or_else(|| config.input.clone())
+        .unwrap_or_else(|| PathBuf::from("."));
+    let archive = args
+        .iter()
+        .zip(args.iter().skip(1))
+        .find(|(flag, _)| *flag == "--archive")
+        .map(|(_, value)| PathBuf::from(value))
+        .or_else(|| config.archive.clone())
+        .unwrap_or_else(|| root.join("archive"));
+
+    if args.get(1).map(String::as_str) == Some("restore") {
+        let run_id = args.iter().zip(args.iter().skip(1)).find(|(flag, _)| *flag == "--run").map(|(_, id)| id.clone());
+        if let Err(e) = restore_run(&root, &archive, run_id.as_deref()) {
+            eprintln!("Error during restore: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let recover = args.iter().any(|a| a == "--recover");
+    let recover_rollback = args.iter().any(|a| a == "--recover-rollback");
+    if recover || recover_rollback {
+        if let Err(e) = recover_journal(&root, &archive, recover_rollback, &generate_run_id()) {
+            eprintln!("Error during recovery: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+    if journal_path(&archive).exists() {
+        eprintln!(
+            "An incomplete cleanup journal exists at {}. The previous run was interrupted.\n\
+             Run with --recover to finish the remaining moves, or --recover-rollback to undo what completed.",
+            journal_path(&archive).display()
+        );
+        std::process::exit(1);
+    }
+
     let dry_run = args.iter().any(|a| a == "--dry-run");
-    let root = PathBuf::from(".");
-    let archive = root.join("archive");
+    let no_ignore = args.iter().any(|a| a == "--no-ignore");
+    let analyze = args.iter().any(|a| a == "--analyze");
+    let dead_code_threshold = args
+        .iter()
+        .zip(args.iter().skip(1))
+        .find(|(flag, _)| *flag == "--dead-code-threshold")
+        .map(|(_, value)| {
+            value.parse().unwrap_or_else(|_| {
+                eprintln!("Invalid --dead-code-threshold '{}': expected a number", value);
+                std::process::exit(1);
+            })
+        })
+        .unwrap_or(DEFAULT_DEAD_CODE_THRESHOLD);
+
+    let cli_excludes: Vec<String> =
+        args.iter().zip(args.iter().skip(1)).filter(|(flag, _)| *flag == "--exclude").map(|(_, g)| g.clone()).collect();
+    let exclude_patterns =
+        if cli_excludes.is_empty() { config.exclude.clone().unwrap_or_default() } else { cli_excludes };
+    let exclude = compile_patterns(&exclude_patterns, "--exclude");
+
+    let include_patterns = config
+        .include
+        .clone()
+        .unwrap_or_else(|| DEFAULT_INCLUDE_PATTERNS.iter().map(|p| p.to_string()).collect());
+    let include = compile_patterns(&include_patterns, "include");
+
+    let keep_directory_structure = if args.iter().any(|a| a == "--flatten") {
+        false
+    } else {
+        config.keep_directory_structure.unwrap_or(true)
+    };
+
+    let opts =
+        CleanupOptions { dry_run, no_ignore, analyze, dead_code_threshold, include, exclude, keep_directory_structure };
+
     if !is_git_clean() {
         eprintln!("Git working directory is not clean. Commit or stash changes before running cleanup.");
         std::process::exit(1);
     }
-    if let Err(e) = find_unused_code(&root, &archive, dry_run) {
+    let run_id = generate_run_id();
+
+    if args.iter().any(|a| a == "--watch") {
+        if let Err(e) = run_watch(&root, &archive, &opts, &run_id) {
+            eprintln!("Error during watch: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if let Err(e) = find_unused_code(&root, &archive, &run_id, &opts) {
         eprintln!("Error during cleanup: {}", e);
         std::process::exit(1);
     }